@@ -1,27 +1,87 @@
 /*
 Module to help split files into digestible chunks for mapreduce.
 */
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::*;
+use crc32fast::Hasher;
 
+/// Size of the read buffer used when streaming a chunk's bytes out of its
+/// source file on demand (see `FileChunk::dump`).
+const BUFFER_SIZE: usize = 64 * 1024;
 
 #[derive(Debug)]
 pub struct FileChunk {
     source: String,
-    path: String,
+    /// Path to the materialized chunk file, or `None` when the chunk was
+    /// produced in zero-copy mode and only exists as a `[start, stop)` byte
+    /// range into `source`.
+    path: Option<String>,
+    /// Start offset (inclusive) of this chunk within `source`, in bytes.
+    ///
+    /// Not meaningful for `RoundRobinChunker` output: round-robin chunks aren't
+    /// contiguous ranges of `source`, so this is always `0` there regardless of
+    /// `index` -- see the struct doc on `RoundRobinChunker`.
+    start: u64,
+    /// End offset (exclusive) of this chunk within `source`, in bytes.
+    ///
+    /// Not meaningful for `RoundRobinChunker` output -- see `start`.
+    stop: u64,
     index: usize,
+    /// CRC32 of the chunk's bytes, computed as they were written. Lets a
+    /// worker that receives this chunk over the network detect silent
+    /// corruption or a partial write via `verify` before processing it.
+    ///
+    /// `None` when the chunk was piped through a `filter` command: the bytes
+    /// hashed while writing to the filter's stdin are the *pre-filter* input,
+    /// not whatever the filter actually produced (and, per its own `$FILE`
+    /// convention, the filter isn't even required to write to `path`), so no
+    /// checksum describing the chunk's real output can be computed here.
+    crc32: Option<u32>,
+    /// Whether this chunk's bytes were piped through a `filter` command rather
+    /// than written directly to `path`. `open`, `true_line_count`, and `verify`
+    /// all assume `path` (or `source`) holds the chunk's actual bytes, which a
+    /// filter is free to violate, so they refuse to operate on filtered chunks.
+    filtered: bool,
+}
+
+/// Writer adapter that feeds every byte it's given through a rolling CRC32
+/// hash instead of storing it, used by `FileChunk::verify` to recompute a
+/// chunk's checksum via the same `dump` path used to read it.
+struct Crc32Writer(Hasher);
+
+impl Write for Crc32Writer {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Context object to use within the chunker. It represents the state of chunking for
 /// a single chunk of a single file.
-/// 
+///
 pub struct ChunkerContext {
     source: String,
-    path: String,
+    path: Option<String>,
     chunk_idx: usize,
     line_i: usize,
     byte_i: usize,
-    writer: LineWriter<File>,
+    /// Offset of the chunk's first byte within `source`.
+    start: u64,
+    writer: Option<LineWriter<Box<dyn Write>>>,
+    /// Rolling CRC32 over the bytes written to this chunk so far. Only meaningful
+    /// when `filtered` is `false` -- see `FileChunk::crc32`.
+    crc32: Hasher,
+    /// Whether this chunk's bytes are piped to a filter command rather than
+    /// written directly to `path`.
+    filtered: bool,
+    /// The filter subprocess this chunk's bytes are piped to, if any. Only ever
+    /// populated on Unix -- see `build_chunker_context`.
+    #[cfg(unix)]
+    child: Option<std::process::Child>,
 }
 
 impl ChunkerContext {
@@ -31,22 +91,39 @@ impl ChunkerContext {
         FileChunk {
             source: self.source.clone(),
             path: self.path.clone(),
+            start: self.start,
+            stop: self.start + self.byte_i as u64,
             index: self.chunk_idx,
+            filtered: self.filtered,
+            crc32: if self.filtered { None } else { Some(self.crc32.clone().finalize()) },
         }
     }
+
+    /// Flushes and closes the chunk's writer, then, if the chunk was piped to a filter
+    /// command, waits on it and errors if it exited non-zero. Must be called once a
+    /// chunk's content has been fully written and before `build_chunk` is relied upon.
+    fn finish(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush()?;
+        }
+        // Dropping the writer closes the underlying file or, for a filter chunk, the
+        // subprocess's stdin so it sees EOF and can exit.
+        self.writer = None;
+
+        #[cfg(unix)]
+        if let Some(mut child) = self.child.take() {
+            let status = child.wait()?;
+            if !status.success() {
+                return Err(Error::new(ErrorKind::Other, format!("filter command exited with {}", status)));
+            }
+        }
+        Ok(())
+    }
 }
 
-/// Creates the initial chunker context object.
-/// 
-/// Chunk files are named `{out}/{chunk_idx}`.
-/// 
-/// # Example
-/// 
-/// ```rust
-/// let c = build_chunker_context("/home/big-file.txt", "/tmp/big-file.chunks/", 0);
-/// ```
-fn build_chunker_context(source: &str, out: &str, chunk_idx: usize) -> ChunkerContext {
-    let chunk_path = format!(
+/// Computes the path a chunk file would live at: `{out}/{chunk_idx}`.
+fn chunk_path(out: &str, chunk_idx: usize) -> String {
+    format!(
         "{}/{}",
         match out.strip_suffix("/") {
             None => {
@@ -58,29 +135,145 @@ fn build_chunker_context(source: &str, out: &str, chunk_idx: usize) -> ChunkerCo
             Some(v) => v
         },
         chunk_idx,
-    );
-    let chunk_file = match File::create(&chunk_path) {
-        Err(_why) => panic!("couldn't create {}: {}", chunk_path, _why),
-        Ok(chunk_file) => chunk_file
-    };
-    ChunkerContext {
+    )
+}
+
+/// Creates the initial chunker context object.
+///
+/// Chunk files are named `{out}/{chunk_idx}`. When `zero_copy` is `true`, no
+/// file is created and the context tracks `source` byte offsets only.
+///
+/// When `filter` is set, the chunk's bytes are piped to `sh -c "<filter>"` instead of
+/// being written to `{out}/{chunk_idx}` directly, with a `FILE` environment variable
+/// pointing the command at that same would-be path (mirroring GNU split's `--filter`).
+/// This is only supported on Unix, since it relies on spawning a shell subprocess.
+///
+/// `zero_copy` and `filter` are mutually exclusive: a zero-copy chunk never has any
+/// bytes to pipe anywhere, so passing both is rejected with an error rather than
+/// silently ignoring `filter`.
+///
+/// # Example
+///
+/// ```rust
+/// let c = build_chunker_context("/home/big-file.txt", "/tmp/big-file.chunks/", 0, 0, false, None).unwrap();
+/// ```
+fn build_chunker_context(source: &str, out: &str, chunk_idx: usize, start: u64, zero_copy: bool, filter: Option<&str>) -> Result<ChunkerContext> {
+    if zero_copy {
+        if filter.is_some() {
+            return Err(Error::new(ErrorKind::InvalidInput, "zero_copy and filter cannot be used together"));
+        }
+        return Ok(ChunkerContext {
+            source: source.to_string(),
+            path: None,
+            chunk_idx,
+            line_i: 0,
+            byte_i: 0,
+            start,
+            writer: None,
+            crc32: Hasher::new(),
+            filtered: false,
+            #[cfg(unix)]
+            child: None,
+        });
+    }
+    let path = chunk_path(out, chunk_idx);
+
+    #[cfg(unix)]
+    if let Some(cmd) = filter {
+        use std::process::{Command, Stdio};
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(cmd)
+            .env("FILE", &path)
+            .stdin(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| Error::new(ErrorKind::Other, "filter command has no stdin"))?;
+        return Ok(ChunkerContext {
+            source: source.to_string(),
+            path: Some(path),
+            chunk_idx,
+            line_i: 0,
+            byte_i: 0,
+            start,
+            writer: Some(LineWriter::new(Box::new(stdin))),
+            crc32: Hasher::new(),
+            filtered: true,
+            child: Some(child),
+        });
+    }
+    #[cfg(not(unix))]
+    if filter.is_some() {
+        return Err(Error::new(ErrorKind::Unsupported, "--filter is not supported on this platform (spawning a filter command is Unix-only)"));
+    }
+
+    let chunk_file = File::create(&path)?;
+    Ok(ChunkerContext {
         source: source.to_string(),
-        path: chunk_path.clone(),
-        chunk_idx: chunk_idx,
+        path: Some(path),
+        chunk_idx,
         line_i: 0,
         byte_i: 0,
-        writer: LineWriter::new(chunk_file)
-    }
+        start,
+        writer: Some(LineWriter::new(Box::new(chunk_file))),
+        crc32: Hasher::new(),
+        filtered: false,
+        #[cfg(unix)]
+        child: None,
+    })
 }
 
 /// A trait for implementing Chunkers. Must be able to chunk a file into smaller files
 pub trait Chunker {
-    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>);
+    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) -> Result<()>;
+}
+
+/// Chunks a whole collection of files into zero-copy chunks, using `SizeBySpaceChunker`'s
+/// whitespace-safe boundaries.
+///
+/// Each file gets a budget of one chunk plus a share of `max_chunks` proportional to its
+/// size, so the budgets add up to about `max_chunks + paths.len()`: a file `k` times the
+/// size of another earns roughly `k` times as many extra chunks, and a file too small to
+/// earn any (`size * max_chunks` below the average size times `paths.len()`) is kept whole.
+/// Each file is chunked with `max_bytes` set to its size divided by its budget, but never
+/// below `min_size`. Because every boundary walks back to the nearest whitespace, a chunk
+/// can come out shorter than `max_bytes` (and so shorter than `min_size`), and a file can
+/// yield more chunks than its budget, so the total is not strictly capped at
+/// `max_chunks + paths.len()`. Chunks never straddle a file boundary.
+pub fn chunkify_multiple(paths: &[String], max_chunks: usize, min_size: usize) -> Result<Vec<FileChunk>> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sizes: Vec<u64> = paths.iter()
+        .map(|p| fs::metadata(p).map(|m| m.len()))
+        .collect::<Result<Vec<u64>>>()?;
+    let total_size: u64 = sizes.iter().sum();
+    let avg_size = std::cmp::max(1, total_size / sizes.len() as u64);
+
+    let mut chunks: Vec<FileChunk> = Vec::new();
+    for (path, size) in paths.iter().zip(sizes.iter()) {
+        // This file's share of `max_chunks`, proportional to its size. A file too small
+        // to earn a whole share gets no extra chunks.
+        let extra_chunks = (size * max_chunks as u64) / (avg_size * paths.len() as u64);
+        let file_chunks = 1 + extra_chunks as usize;
+        let max_bytes = std::cmp::max(min_size.max(1), (*size as usize) / file_chunks);
+
+        let chunker = SizeBySpaceChunker { max_bytes, zero_copy: true, filter: None };
+        chunker.chunk(path, "", &mut chunks)?;
+    }
+    Ok(chunks)
 }
 
 /// A chunker that divides a file into equitable line counts.
 pub struct LineChunker {
-    pub max_lines: usize
+    pub max_lines: usize,
+    /// When `true`, chunks are recorded as `[start, stop)` byte ranges into
+    /// the source file instead of being copied to `out/`.
+    pub zero_copy: bool,
+    /// Optional `sh -c` command each chunk's bytes are piped to instead of being
+    /// written to `out/{chunk_idx}` directly. Unix-only. Mutually exclusive with
+    /// `zero_copy`; `chunk` errors if both are set.
+    pub filter: Option<String>,
 }
 
 #[allow(private_interfaces)]
@@ -90,57 +283,59 @@ impl Chunker for LineChunker {
     /// Chunks a file into smaller files with no more than `max_lines` per chunk.
     ///
     /// # Example
-    /// 
+    ///
     /// ```rust
     /// use chunker;
     /// let src = String::from("/home/file.txt");
     /// let dir = String::from("/tmp/file.txt.chunks/");
     /// let mut chunks: Vec<chunker::FileChunk> = Vec::new();
-    /// let chnkr: chunker::LineChunker = LineChunker{max_lines: 100};
-    /// chnkr.chunk(&src, &dir, 150_000, &mut chunks);
+    /// let chnkr: chunker::LineChunker = LineChunker{max_lines: 100, zero_copy: false, filter: None};
+    /// chnkr.chunk(&src, &dir, &mut chunks).unwrap();
     /// ```
-    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) {
-        let file = match File::open(&path) {
-            Err(_why) => panic!("couldn't open {}: {}", path, _why),
-            Ok(file) => file,
-        };
-        let file = BufReader::new(file);
+    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) -> Result<()> {
+        if self.zero_copy && self.filter.is_some() {
+            return Err(Error::new(ErrorKind::InvalidInput, "zero_copy and filter cannot be used together"));
+        }
+        let file = BufReader::new(File::open(path)?);
         let mut lines = file.lines();
         let mut chunk_idx: usize = 0;
+        let mut source_byte_i: u64 = 0;
 
         'chunk_loop: loop {
             // Running this routine for each chunk. Iterates over `lines` and saves it to the chunk
             // until the max is hit or the source file is exhausted
-            let mut ctx: ChunkerContext = build_chunker_context(path, out, chunk_idx);
+            let mut ctx: ChunkerContext = build_chunker_context(path, out, chunk_idx, source_byte_i, self.zero_copy, self.filter.as_deref())?;
             while ctx.line_i < self.max_lines {
                 // Get the next line. If None, the buffer is exhausted and loop should be terminated
                 let line = match lines.next() {
                     None => {
                         // Add the chunk before exiting loop scope if there's anything in it
+                        ctx.finish()?;
                         if ctx.byte_i > 0 {
                             chunks.push(ctx.build_chunk());
                         };
                         break 'chunk_loop
                     },
-                    Some(line) => {
-                        match line {
-                            Err(_why) => panic!("couldn't read line {}: {}", ctx.line_i, _why),
-                            Ok(v) => v
-                        }
-                    },
+                    Some(line) => line?,
                 };
 
                 // Write the line and increment
-                ctx.writer.write_all(line.as_bytes()).expect(format!("Failed to write line {} to {}", ctx.line_i, ctx.path).as_str());
-                ctx.writer.write_all(b"\n").expect(format!("Failed to write linebreak at {} to {}", ctx.line_i, ctx.path).as_str());
+                if let Some(writer) = ctx.writer.as_mut() {
+                    writer.write_all(line.as_bytes())?;
+                    writer.write_all(b"\n")?;
+                }
+                ctx.crc32.update(line.as_bytes());
+                ctx.crc32.update(b"\n");
                 ctx.line_i += 1;
                 ctx.byte_i += line.len() + 1;
             }
-            ctx.writer.flush().expect(format!("Failed to flush {}", ctx.path).as_str());
+            ctx.finish()?;
             // Add the completed chunk
+            source_byte_i += ctx.byte_i as u64;
             chunks.push(ctx.build_chunk());
             chunk_idx += 1;
         }
+        Ok(())
     }
 
 }
@@ -149,31 +344,40 @@ impl Chunker for LineChunker {
 /// breaks a contiguous word. A chunk can only end with a whitespace
 /// character.
 pub struct SizeBySpaceChunker {
-    pub max_bytes: usize
+    pub max_bytes: usize,
+    /// When `true`, chunks are recorded as `[start, stop)` byte ranges into
+    /// the source file instead of being copied to `out/`.
+    pub zero_copy: bool,
+    /// Optional `sh -c` command each chunk's bytes are piped to instead of being
+    /// written to `out/{chunk_idx}` directly. Unix-only. Mutually exclusive with
+    /// `zero_copy`; `chunk` errors if both are set.
+    pub filter: Option<String>,
 }
 
 impl Chunker for SizeBySpaceChunker {
 
     /// Splits a file into chunks of sizes no larger than `max_bytes` and along whitespace.
-    /// 
+    ///
     /// # Example
     /// ```rust
     /// use chunker::{FileChunk, SizeBySpaceChunker};
     /// let src = String::from("/home/file.txt");
     /// let dir = String::from("/tmp/file.txt.chunks/");
     /// let mut chunks: Vec<FileChunk> = Vec::new();
-    /// let chnkr: SizeBySpaceChunker = SizeBySpaceChunker{max_bytes: 2 ^ 20};
-    /// chnkr.chunk(&src, &dir, 150_000, &mut chunks);
+    /// let chnkr: SizeBySpaceChunker = SizeBySpaceChunker{max_bytes: 2 ^ 20, zero_copy: false, filter: None};
+    /// chnkr.chunk(&src, &dir, &mut chunks).unwrap();
     /// ```
-    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) {
-        let file = File::open(path).expect(format!("Unable to read {}", path).as_str());
-        let mut reader = BufReader::new(file);
-        let mut writer: BufWriter<File>;
+    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) -> Result<()> {
+        if self.zero_copy && self.filter.is_some() {
+            return Err(Error::new(ErrorKind::InvalidInput, "zero_copy and filter cannot be used together"));
+        }
+        let mut reader = BufReader::new(File::open(path)?);
 
         let mut remainder: Vec<u8> = Vec::with_capacity(self.max_bytes);
         let mut buf = vec![0u8; self.max_bytes];
         let mut byte_i: usize;
-        let mut ctx = build_chunker_context(path, out, 0);
+        let mut chunk_idx: usize = 0;
+        let mut source_byte_i: u64 = 0;
         loop {
             // If there's a remainder from the last iter, the buffer should only read in
             // a limited amount (`right`). If there is no remainder, left will be empty.
@@ -187,10 +391,7 @@ impl Chunker for SizeBySpaceChunker {
             }
 
             // Read values into the remaining allocated array space
-            let count = match reader.read(right) {
-                Err(_why) => panic!("{} | Failed to read bytes", path),
-                Ok(v) => v                
-            };
+            let count = reader.read(right)?;
 
             // When not having read EOF, this should equal the max size
             // The exception is when the source byte count is perfectly divisible by the chunk
@@ -199,8 +400,14 @@ impl Chunker for SizeBySpaceChunker {
             if non_empty_len == 0 {break}
 
             byte_i = non_empty_len - 1;
-            // Decrement until a non-whitespace character is reached
-            while !buf[byte_i].is_ascii_whitespace() {byte_i -= 1;}
+            // Decrement until a whitespace character is reached, but never past index 0
+            while byte_i > 0 && !buf[byte_i].is_ascii_whitespace() {byte_i -= 1;}
+            if !buf[byte_i].is_ascii_whitespace() {
+                // No whitespace anywhere in this read (e.g. one contiguous run longer
+                // than max_bytes) -- there's no safe place to split, so flush the whole
+                // buffer as this chunk instead of carrying it forward forever.
+                byte_i = non_empty_len - 1;
+            }
 
             // If the byte index was walked back, add the excluded values to the remainder
             // so they are handled in the next iteration. Exclude the whitespace from the
@@ -209,45 +416,259 @@ impl Chunker for SizeBySpaceChunker {
                 remainder.append(&mut buf[byte_i+1..non_empty_len].to_vec());
             }
 
-            // Create the chunk
-            writer = BufWriter::new(
-                match File::create(&ctx.path) {
-                    Err(_why) => panic!("couldn't create {}: {}", ctx.path, _why),
-                    Ok(chunk_file) => chunk_file
-                }
-            );
-            writer.write(&buf[..byte_i+1]).expect("Failed to write chunk");
-            writer.flush().expect("Failed to write chunk");
-            chunks.push(ctx.build_chunk());
+            let mut crc = Hasher::new();
+            crc.update(&buf[..byte_i+1]);
+
+            // Materialize the chunk unless running in zero-copy mode, in which case only
+            // the `[start, stop)` byte range into `path` is recorded.
+            let path_opt = if self.zero_copy {
+                None
+            } else {
+                let mut ctx = build_chunker_context(path, out, chunk_idx, source_byte_i, false, self.filter.as_deref())?;
+                ctx.writer.as_mut().unwrap().write_all(&buf[..byte_i+1])?;
+                ctx.finish()?;
+                ctx.path
+            };
+            let filtered = self.filter.is_some();
+            let stop = source_byte_i + (byte_i as u64 + 1);
+            chunks.push(FileChunk {
+                source: path.to_string(),
+                path: path_opt,
+                start: source_byte_i,
+                stop,
+                index: chunk_idx,
+                filtered,
+                // A filter command receives the pre-filter bytes hashed into `crc`, not
+                // whatever it actually produced, so the checksum can't describe the
+                // chunk's real output -- see `FileChunk::crc32`.
+                crc32: if filtered { None } else { Some(crc.finalize()) },
+            });
+            source_byte_i = stop;
 
             // If the remainder is empty and the previously written bytes didn't add up
             // to the total buffer size, we are at the end of the file and are done
             // chunking
             if non_empty_len < self.max_bytes {break};
-            // Reset, creating the next context
-            ctx = build_chunker_context(path, out, ctx.chunk_idx + 1);
+            // Reset for the next chunk
+            chunk_idx += 1;
             buf.fill(0);
         }
+        Ok(())
+    }
+}
+
+/// A chunker that distributes lines cyclically across `n` chunks: line `i` goes to
+/// chunk `i % n`. Models GNU split's `-n r/K/N` round-robin assignment.
+///
+/// Because a round-robin chunk's lines are scattered non-contiguously across
+/// `source`, the `FileChunk`s this produces carry a `start` of `0` and a `stop`
+/// equal to the chunk's byte length rather than a real byte range -- always use
+/// `path`/`dump` to read these chunks, never `start`/`stop`.
+pub struct RoundRobinChunker {
+    pub n: usize,
+    /// If set, only the 1-indexed `K`th of `n` chunks is materialized, mirroring
+    /// split's `-n r/K/N`. When `None`, all `n` chunks are written.
+    pub select: Option<usize>,
+    /// Optional `sh -c` command each chunk's bytes are piped to instead of being
+    /// written to `out/{chunk_idx}` directly. Unix-only.
+    pub filter: Option<String>,
+}
+
+impl Chunker for RoundRobinChunker {
+
+    /// Distributes the source file's lines round-robin across `n` chunks, opening
+    /// a `LineWriter` only for the chunk(s) actually wanted so a caller can request a
+    /// single shard (`select`) without the other `n - 1` ever touching disk.
+    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) -> Result<()> {
+        if self.n == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "n must be at least 1"));
+        }
+        let wanted: Vec<usize> = match self.select {
+            Some(k) => {
+                if k < 1 || k > self.n {
+                    return Err(Error::new(ErrorKind::InvalidInput, format!("selection {} out of range for {} chunks", k, self.n)));
+                }
+                vec![k - 1]
+            },
+            None => (0..self.n).collect(),
+        };
 
+        let mut ctxs: Vec<ChunkerContext> = Vec::with_capacity(wanted.len());
+        for idx in wanted {
+            // `start: 0` is a placeholder, not a real source offset -- round-robin
+            // output isn't contiguous in `source`, so no single range describes it.
+            // See the struct doc on `RoundRobinChunker` and `FileChunk::start`.
+            ctxs.push(build_chunker_context(path, out, idx, 0, false, self.filter.as_deref())?);
+        }
+
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let mut line_i: usize = 0;
+        loop {
+            let line = match lines.next() {
+                None => break,
+                Some(line) => line?,
+            };
+
+            let target = line_i % self.n;
+            if let Some(ctx) = ctxs.iter_mut().find(|c| c.chunk_idx == target) {
+                let writer = ctx.writer.as_mut().expect("round-robin chunks are always materialized");
+                writer.write_all(line.as_bytes())?;
+                writer.write_all(b"\n")?;
+                ctx.crc32.update(line.as_bytes());
+                ctx.crc32.update(b"\n");
+                ctx.line_i += 1;
+                ctx.byte_i += line.len() + 1;
+            }
+            line_i += 1;
+        }
+
+        for ctx in ctxs.iter_mut() {
+            ctx.finish()?;
+        }
+        for ctx in ctxs.iter() {
+            chunks.push(ctx.build_chunk());
+        }
+        Ok(())
+    }
+}
+
+/// A chunker that divides a file into exactly `n` chunks by byte count, with sizes
+/// differing by at most one byte. Models GNU split's `-n N` (fixed count by byte).
+pub struct ByteCountChunker {
+    pub n: usize,
+    /// Optional `sh -c` command each chunk's bytes are piped to instead of being
+    /// written to `out/{chunk_idx}` directly. Unix-only.
+    pub filter: Option<String>,
+}
+
+impl Chunker for ByteCountChunker {
+
+    /// Splits a file into exactly `n` chunks: the first `len % n` chunks get
+    /// `len / n + 1` bytes, the rest get `len / n`. Unlike `SizeBySpaceChunker` this
+    /// does not preserve word boundaries -- it may split mid-word -- so it's meant for
+    /// fixed-fanout mapreduce where the map function tolerates arbitrary byte splits.
+    /// If `n` exceeds the file's byte count, the trailing chunks are empty, matching
+    /// GNU split's guarantee that exactly `n` files are always produced.
+    fn chunk(&self, path: &str, out: &str, chunks: &mut Vec<FileChunk>) -> Result<()> {
+        if self.n == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "n must be at least 1"));
+        }
+        let len = fs::metadata(path)?.len();
+        let base = len / self.n as u64;
+        let rem = len % self.n as u64;
+
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut start: u64 = 0;
+
+        for chunk_idx in 0..self.n {
+            let size = base + if (chunk_idx as u64) < rem {1} else {0};
+            let mut ctx = build_chunker_context(path, out, chunk_idx, start, false, self.filter.as_deref())?;
+            let mut remaining = size;
+            let mut buf = vec![0u8; std::cmp::min(BUFFER_SIZE as u64, size) as usize];
+            while remaining > 0 {
+                let to_read = std::cmp::min(buf.len() as u64, remaining) as usize;
+                let n_read = reader.read(&mut buf[..to_read])?;
+                if n_read == 0 {break};
+                ctx.writer.as_mut().unwrap().write_all(&buf[..n_read])?;
+                ctx.crc32.update(&buf[..n_read]);
+                ctx.byte_i += n_read;
+                remaining -= n_read as u64;
+            }
+            ctx.finish()?;
+            start += size;
+            chunks.push(ctx.build_chunk());
+        }
+        Ok(())
     }
 }
 
 #[allow(dead_code)]
 impl FileChunk {
 
+    /// CRC32 of this chunk's bytes, computed as it was written. Compare against
+    /// `verify()`'s recomputed checksum to detect corruption or a partial write.
+    ///
+    /// `None` for chunks piped through a `filter` command, since no checksum
+    /// describing the filter's actual output was ever computed -- see the field
+    /// doc on `FileChunk::crc32`.
+    pub fn crc32(&self) -> Option<u32> {
+        self.crc32
+    }
+
+    /// Re-reads this chunk's bytes (via `dump`) and recomputes their CRC32,
+    /// returning whether it matches the checksum recorded when the chunk was
+    /// written. Lets a worker that just received a chunk over the network
+    /// detect silent corruption before processing it.
+    ///
+    /// Always returns `false` for a filter-backed chunk: there is no recorded
+    /// checksum to compare against, and `path` isn't even guaranteed to hold the
+    /// filter's output (the filter may have written elsewhere entirely).
+    pub fn verify(&self) -> bool {
+        let Some(expected) = self.crc32 else { return false };
+        let mut w = Crc32Writer(Hasher::new());
+        match self.dump(&mut w) {
+            Ok(()) => w.0.finalize() == expected,
+            Err(_) => false,
+        }
+    }
+
     pub fn true_line_count(&self) -> usize {
         let lines = BufReader::new(self.open()).lines();
         lines.count()
     }
 
-    /// Opens the file and returns it
+    /// Opens the materialized chunk file and returns it. Panics if this chunk was
+    /// produced in zero-copy mode (no `path`) -- use `dump` instead -- or if it was
+    /// piped through a `filter` command, since `path` isn't guaranteed to hold the
+    /// filter's output at all (the filter may have written elsewhere entirely, the
+    /// way `wc -w > "$FILE.counts"` does).
     pub fn open(&self) -> File {
-        match File::open(&self.path) {
-            Err(_why) => panic!("couldn't open {}: {}", self.path, _why),
+        if self.filtered {
+            panic!("chunk was piped through a filter command; its bytes may not be at the recorded path -- read the filter's own output instead");
+        }
+        let path = self.path.as_ref().expect("chunk has no materialized path; use dump() instead");
+        match File::open(path) {
+            Err(_why) => panic!("couldn't open {}: {}", path, _why),
             Ok(file) => file,
         }
     }
 
+    /// Streams this chunk's bytes into `w`.
+    ///
+    /// If the chunk was materialized (has a `path`), its file is copied verbatim.
+    /// Otherwise, this seeks into `source` at `start` and reads up to `stop`,
+    /// capping each read at `BUFFER_SIZE` so arbitrarily large chunks don't need
+    /// to be buffered in memory all at once. This lets a mapreduce worker pull
+    /// its assigned split directly out of the original file on demand.
+    pub fn dump<W: Write>(&self, mut w: W) -> Result<()> {
+        if let Some(path) = &self.path {
+            let mut reader = BufReader::new(File::open(path)?);
+            copy(&mut reader, &mut w)?;
+            return Ok(());
+        }
+
+        let mut source = File::open(&self.source)?;
+        source.seek(SeekFrom::Start(self.start))?;
+
+        let total = self.stop - self.start;
+        let mut buf = vec![0u8; std::cmp::min(BUFFER_SIZE as u64, total) as usize];
+        let mut current_byte: u64 = 0;
+        while current_byte < total {
+            let remaining = (total - current_byte) as usize;
+            let to_read = std::cmp::min(buf.len(), remaining);
+            let n = source.read(&mut buf[..to_read])?;
+            if n == 0 {
+                // Source is shorter than expected (e.g. truncated after chunking);
+                // nothing left to stream, including when the final chunk ends
+                // without a trailing newline.
+                break;
+            }
+            w.write_all(&buf[..n])?;
+            current_byte += n as u64;
+        }
+        Ok(())
+    }
+
 }
 
 
@@ -293,14 +714,14 @@ mod tests {
         let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
 
         let mut chunks: Vec<FileChunk> = Vec::new();
-        let chunker = LineChunker {max_lines: 1000};
+        let chunker = LineChunker {max_lines: 1000, zero_copy: false, filter: None};
         chunker.chunk(
             &path,
             tmp.path().to_str().expect("Failed to convert tempdir path to string"),
             &mut chunks
-        );
+        ).expect("Failed to chunk file");
 
-        // Assert 
+        // Assert
         // 1. Number of chunks
         // 2. Reported lines per chunk
         // 3. True lines per chunk
@@ -321,12 +742,12 @@ mod tests {
         let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
 
         let mut chunks: Vec<FileChunk> = Vec::new();
-        let chunker = LineChunker {max_lines: 1000};
+        let chunker = LineChunker {max_lines: 1000, zero_copy: false, filter: None};
         chunker.chunk(
             &path,
             tmp.path().to_str().expect("Failed to convert tempdir path to string"),
             &mut chunks
-        );
+        ).expect("Failed to chunk file");
 
         // Assert
         // 1. Number of chunks
@@ -373,12 +794,12 @@ mod tests {
         println!("{}", path);
         let mut chunks: Vec<FileChunk> = Vec::new();
         // Up to 32 mebi
-        let chunker = SizeBySpaceChunker {max_bytes: MULT_2};
+        let chunker = SizeBySpaceChunker {max_bytes: MULT_2, zero_copy: false, filter: None};
         chunker.chunk(
             &path,
             tmp.path().to_str().expect("Failed to convert tempdir path to string"),
             &mut chunks
-        );
+        ).expect("Failed to chunk file");
         assert!(chunks.len() == 2);
         let mut total: usize = 0;
         for chnk in chunks.iter() {
@@ -400,12 +821,12 @@ mod tests {
 
         let mut chunks: Vec<FileChunk> = Vec::new();
         // Up to 32 mebi
-        let chunker = SizeBySpaceChunker {max_bytes: MULT_2};
+        let chunker = SizeBySpaceChunker {max_bytes: MULT_2, zero_copy: false, filter: None};
         chunker.chunk(
             &path,
             tmp.path().to_str().expect("Failed to convert tempdir path to string"),
             &mut chunks
-        );
+        ).expect("Failed to chunk file");
         assert_eq!(chunks.len(), 3);
         let mut total: usize = 0;
         for (i, chnk) in chunks.iter().enumerate() {
@@ -423,5 +844,299 @@ mod tests {
         tmp.close().expect("Failed to close the expected directory.");
     }
 
+    #[rstest]
+    fn test_chunk_by_line_count_zero_copy_dump(tmp: TempDir) {
+        const LINES_IN_TEST_FILE: usize = 2_500;
+        let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = LineChunker {max_lines: 1000, zero_copy: true, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
 
-}
\ No newline at end of file
+        assert_eq!(chunks.len(), 3);
+        let mut total_lines = 0;
+        for chnk in chunks.iter() {
+            let mut out: Vec<u8> = Vec::new();
+            chnk.dump(&mut out).expect("Failed to dump chunk");
+            total_lines += BufReader::new(out.as_slice()).lines().count();
+        }
+        assert_eq!(total_lines, LINES_IN_TEST_FILE);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_chunk_by_size_whitespace_zero_copy_dump(tmp: TempDir) {
+        const MULT_2: usize = 32;
+        const BYTES_IN_TEST_FILE: usize = MULT_2 * 2 + 4;
+        let path = many_byte_file(&tmp, BYTES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = SizeBySpaceChunker {max_bytes: MULT_2, zero_copy: true, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), 3);
+        let mut total: usize = 0;
+        for chnk in chunks.iter() {
+            let mut out: Vec<u8> = Vec::new();
+            chnk.dump(&mut out).expect("Failed to dump chunk");
+            total += out.len();
+        }
+        assert_eq!(total, BYTES_IN_TEST_FILE);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_chunkify_multiple_gives_bigger_files_more_chunks(tmp: TempDir) {
+        let small_path = many_byte_file(&tmp, 64);
+        let big_path = many_byte_file(&tmp, 640);
+        let paths = vec![small_path, big_path.clone()];
+
+        let chunks = chunkify_multiple(&paths, 8, 16).expect("Failed to chunk files");
+
+        assert!(chunks.len() > 2);
+        let big_chunks = chunks.iter().filter(|c| c.source == big_path).count();
+        let small_chunks = chunks.len() - big_chunks;
+        assert!(big_chunks > small_chunks);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_round_robin_chunker_distributes_all(tmp: TempDir) {
+        const LINES_IN_TEST_FILE: usize = 99;
+        const N: usize = 3;
+        let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = RoundRobinChunker {n: N, select: None, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), N);
+        let mut total_lines = 0;
+        for chnk in chunks.iter() {
+            assert_eq!(chnk.true_line_count(), LINES_IN_TEST_FILE / N);
+            total_lines += chnk.true_line_count();
+        }
+        assert_eq!(total_lines, LINES_IN_TEST_FILE);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_round_robin_chunker_selects_single_kth_chunk(tmp: TempDir) {
+        const LINES_IN_TEST_FILE: usize = 99;
+        const N: usize = 3;
+        let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = RoundRobinChunker {n: N, select: Some(2), filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        // Only the requested chunk is materialized
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].true_line_count(), LINES_IN_TEST_FILE / N);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_byte_count_chunker_exact_n_with_remainder(tmp: TempDir) {
+        const BYTES_IN_TEST_FILE: usize = 100;
+        const N: usize = 7;
+        let path = many_byte_file(&tmp, BYTES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = ByteCountChunker {n: N, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), N);
+        let base = BYTES_IN_TEST_FILE / N;
+        let rem = BYTES_IN_TEST_FILE % N;
+        let mut total: usize = 0;
+        for (i, chnk) in chunks.iter().enumerate() {
+            let mut buf = BufReader::new(chnk.open());
+            let mut bytes = vec![0u8; BYTES_IN_TEST_FILE];
+            let u = buf.read(&mut bytes).expect("Failed to read");
+            total += u;
+            let expected = base + if i < rem {1} else {0};
+            assert_eq!(u, expected);
+        }
+        assert_eq!(total, BYTES_IN_TEST_FILE);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_byte_count_chunker_n_exceeds_byte_count(tmp: TempDir) {
+        const BYTES_IN_TEST_FILE: usize = 4;
+        const N: usize = 10;
+        let path = many_byte_file(&tmp, BYTES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = ByteCountChunker {n: N, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), N);
+        let empty_chunks = chunks.iter().filter(|c| c.true_line_count() == 0).count();
+        assert!(empty_chunks > 0);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn test_line_chunker_filter_pipes_chunk_to_subprocess(tmp: TempDir) {
+        const LINES_IN_TEST_FILE: usize = 1_000;
+        let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = LineChunker {
+            max_lines: 1000,
+            zero_copy: false,
+            filter: Some("cat > \"$FILE\"".to_string()),
+        };
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), 1);
+        // `open()`/`true_line_count()` refuse filter-backed chunks (see the next two
+        // tests), so read the path the filter was told to write to directly instead.
+        let written = fs::read_to_string(chunks[0].path.as_ref().unwrap()).expect("Failed to read filter output");
+        assert_eq!(written.lines().count(), LINES_IN_TEST_FILE);
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn test_line_chunker_filter_with_transforming_command_disables_verification(tmp: TempDir) {
+        const LINES_IN_TEST_FILE: usize = 10;
+        let path = many_line_file(&tmp, LINES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = LineChunker {
+            max_lines: 1000,
+            zero_copy: false,
+            // Transforms the chunk's bytes (and writes to `$FILE.counts`, a path
+            // other than `$FILE`) -- unlike the passthrough `cat` filter above,
+            // nothing at the recorded `path` holds the chunk's pre-filter bytes.
+            filter: Some("wc -w > \"$FILE.counts\"".to_string()),
+        };
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), 1);
+        // No checksum describing the filter's real output was ever computed.
+        assert_eq!(chunks[0].crc32(), None);
+        // Can't be verified: there's no recorded checksum, and `path` was never
+        // written to by this filter at all.
+        assert!(!chunks[0].verify());
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    #[should_panic(expected = "filter")]
+    fn test_open_panics_on_filter_backed_chunk(tmp: TempDir) {
+        let path = many_line_file(&tmp, 10);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = LineChunker {
+            max_lines: 1000,
+            zero_copy: false,
+            filter: Some("wc -w > \"$FILE.counts\"".to_string()),
+        };
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        chunks[0].open();
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn test_line_chunker_filter_propagates_nonzero_exit(tmp: TempDir) {
+        let path = many_line_file(&tmp, 10);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = LineChunker {
+            max_lines: 1000,
+            zero_copy: false,
+            filter: Some("exit 1".to_string()),
+        };
+        let result = chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        );
+        assert!(result.is_err());
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_materialized_chunk_verifies_against_its_checksum(tmp: TempDir) {
+        let path = many_line_file(&tmp, 200);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = LineChunker {max_lines: 1000, zero_copy: false, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].verify());
+
+        // Corrupting the chunk file after the fact should be caught by verify
+        fs::write(chunks[0].path.as_ref().unwrap(), b"corrupted").expect("Failed to corrupt chunk");
+        assert!(!chunks[0].verify());
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+    #[rstest]
+    fn test_zero_copy_chunk_verifies_against_its_checksum(tmp: TempDir) {
+        const BYTES_IN_TEST_FILE: usize = 68;
+        let path = many_byte_file(&tmp, BYTES_IN_TEST_FILE);
+
+        let mut chunks: Vec<FileChunk> = Vec::new();
+        let chunker = SizeBySpaceChunker {max_bytes: 32, zero_copy: true, filter: None};
+        chunker.chunk(
+            &path,
+            tmp.path().to_str().expect("Failed to convert tempdir path to string"),
+            &mut chunks
+        ).expect("Failed to chunk file");
+
+        for chnk in chunks.iter() {
+            assert!(chnk.verify());
+        }
+        tmp.close().expect("Failed to close the expected directory.");
+    }
+
+}